@@ -0,0 +1,93 @@
+//! Resolved configuration controlling how recipes are rendered and where
+//! generated output is written.
+use chrono::Local;
+use serde::Deserialize;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_MAIN_FONT: &str = "Andika";
+const DEFAULT_FONT_SIZE: u32 = 12;
+const DEFAULT_IMAGE_HEIGHT: &str = "3in";
+
+/// Rendering and output configuration, fully resolved from defaults, an
+/// optional config file, and CLI overrides.
+pub struct Config {
+    /// Main document font (LaTeX output only)
+    pub main_font: String,
+    /// Document font size in points (LaTeX output only)
+    pub font_size: u32,
+    /// Recipe image height, e.g. "3in" (LaTeX output only)
+    pub image_height: String,
+    /// Directory generated output is written into
+    pub output_dir: PathBuf,
+}
+
+/// The subset of `Config` that can come from a TOML file; every field is
+/// optional so a config file only needs to specify the overrides it cares
+/// about.
+#[derive(Deserialize, Default)]
+struct FileConfig {
+    main_font: Option<String>,
+    font_size: Option<u32>,
+    image_height: Option<String>,
+    output_dir: Option<String>,
+}
+
+/// CLI overrides for rendering configuration, layered on top of any config file.
+#[derive(Default)]
+pub struct ConfigOverrides {
+    pub config_file: Option<PathBuf>,
+    pub main_font: Option<String>,
+    pub font_size: Option<u32>,
+    pub image_height: Option<String>,
+    pub output_dir: Option<String>,
+}
+
+/// Read and parse an optional TOML config file.
+///
+/// # Returns
+/// * On success, the parsed FileConfig.
+/// * On Failure, an Err() containing (potentially) useful information is returned.
+fn load_file_config(path: &Path) -> Result<FileConfig, Box<dyn Error>> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("Could not read config file {}: {error}", path.display()))?;
+
+    toml::from_str(&contents)
+        .map_err(|error| format!("Could not parse config file {}: {error}", path.display()).into())
+}
+
+/// Resolve the final rendering configuration: defaults, then an optional TOML
+/// config file, then CLI flags, each layer overriding the one before it.
+///
+/// # Returns
+/// * On success, the fully resolved Config.
+/// * On Failure, an Err() containing (potentially) useful information is returned.
+///
+pub fn resolve(overrides: ConfigOverrides) -> Result<Config, Box<dyn Error>> {
+    let file_config = match &overrides.config_file {
+        Some(path) => load_file_config(path)?,
+        None => FileConfig::default(),
+    };
+
+    let output_dir = overrides
+        .output_dir
+        .or(file_config.output_dir)
+        .unwrap_or_else(|| Local::now().format("%Y%m%d").to_string());
+
+    Ok(Config {
+        main_font: overrides
+            .main_font
+            .or(file_config.main_font)
+            .unwrap_or_else(|| DEFAULT_MAIN_FONT.to_string()),
+        font_size: overrides
+            .font_size
+            .or(file_config.font_size)
+            .unwrap_or(DEFAULT_FONT_SIZE),
+        image_height: overrides
+            .image_height
+            .or(file_config.image_height)
+            .unwrap_or_else(|| DEFAULT_IMAGE_HEIGHT.to_string()),
+        output_dir: PathBuf::from(output_dir),
+    })
+}
@@ -0,0 +1,202 @@
+/// Module to aggregate a week's scraped ingredient lines into a consolidated
+/// shopping list.
+use crate::config::Config;
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::io::prelude::*;
+
+/// An ingredient line, broken down into its quantity, unit, and name.
+struct ParsedIngredient {
+    /// The amount required, if one could be parsed from the line
+    quantity: Option<f64>,
+    /// The unit the quantity is in, if a known one was found
+    unit: Option<String>,
+    /// The ingredient's name, lowercased and trimmed for grouping
+    name: String,
+}
+
+/// Parse a unicode or ASCII fraction/number token, e.g. "1/2" or "½", into a f64.
+fn parse_fraction(token: &str) -> Option<f64> {
+    match token {
+        "¼" => Some(0.25),
+        "½" => Some(0.5),
+        "¾" => Some(0.75),
+        "⅓" => Some(1.0 / 3.0),
+        "⅔" => Some(2.0 / 3.0),
+        "⅕" => Some(0.2),
+        "⅖" => Some(0.4),
+        "⅗" => Some(0.6),
+        "⅘" => Some(0.8),
+        "⅛" => Some(0.125),
+        "⅜" => Some(0.375),
+        "⅝" => Some(0.625),
+        "⅞" => Some(0.875),
+        other => match other.split_once('/') {
+            Some((num, den)) => {
+                let num: f64 = num.parse().ok()?;
+                let den: f64 = den.parse().ok()?;
+                if den == 0.0 {
+                    None
+                } else {
+                    Some(num / den)
+                }
+            }
+            None => other.parse().ok(),
+        },
+    }
+}
+
+/// Parse the leading quantity off a list of whitespace-separated words, e.g.
+/// `["1", "1/2", "cups", "flour"]` parses "1 1/2" as 1.5.
+///
+/// # Returns
+/// * The parsed quantity (if any), and the number of leading words it consumed.
+fn parse_quantity(words: &[&str]) -> (Option<f64>, usize) {
+    let first = match words.first().and_then(|word| parse_fraction(word)) {
+        Some(value) => value,
+        None => return (None, 0),
+    };
+
+    // A second fraction immediately after a whole number, e.g. "1 1/2" or "1 ½"
+    match words.get(1) {
+        Some(second) if second.contains('/') || !second.is_ascii() => match parse_fraction(second) {
+            Some(value) => (Some(first + value), 2),
+            None => (Some(first), 1),
+        },
+        _ => (Some(first), 1),
+    }
+}
+
+/// Normalize a unit word/abbreviation to its canonical short form.
+fn normalize_unit(word: &str) -> Option<&'static str> {
+    match word.to_lowercase().trim_end_matches('.') {
+        "cup" | "cups" => Some("cup"),
+        "tbsp" | "tablespoon" | "tablespoons" => Some("tbsp"),
+        "tsp" | "teaspoon" | "teaspoons" => Some("tsp"),
+        "oz" | "ounce" | "ounces" => Some("oz"),
+        "lb" | "lbs" | "pound" | "pounds" => Some("lb"),
+        "clove" | "cloves" => Some("clove"),
+        "can" | "cans" => Some("can"),
+        "package" | "packages" | "pkg" => Some("package"),
+        "pinch" | "pinches" => Some("pinch"),
+        "quart" | "quarts" | "qt" => Some("quart"),
+        "pint" | "pints" | "pt" => Some("pint"),
+        "gallon" | "gallons" | "gal" => Some("gallon"),
+        "slice" | "slices" => Some("slice"),
+        "stalk" | "stalks" => Some("stalk"),
+        "head" | "heads" => Some("head"),
+        "bunch" | "bunches" => Some("bunch"),
+        _ => None,
+    }
+}
+
+/// Parse a single ingredient line into its quantity, unit, and name.
+fn parse_ingredient(line: &str) -> ParsedIngredient {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    let (quantity, consumed) = parse_quantity(&words);
+    let rest = &words[consumed..];
+
+    let (unit, rest) = match rest.first().and_then(|word| normalize_unit(word)) {
+        Some(unit) => (Some(unit.to_string()), &rest[1..]),
+        None => (None, rest),
+    };
+
+    ParsedIngredient {
+        quantity,
+        unit,
+        name: rest.join(" ").trim().to_lowercase(),
+    }
+}
+
+/// Group ingredient lines by normalized name and unit, summing their quantities.
+///
+/// Lines we couldn't find a leading quantity for (e.g. "salt and pepper to
+/// taste") are assumed to be pantry staples rather than things to count and
+/// are returned separately, unmodified.
+///
+/// # Returns
+/// * A Vec of grouped, summed ingredients, and a Vec of the unparseable lines.
+fn aggregate_ingredients(ingredients: Vec<String>) -> (Vec<ParsedIngredient>, Vec<String>) {
+    let mut grouped: Vec<ParsedIngredient> = Vec::new();
+    let mut pantry: Vec<String> = Vec::new();
+
+    for line in ingredients {
+        let parsed = parse_ingredient(&line);
+
+        let quantity = match parsed.quantity {
+            Some(quantity) => quantity,
+            None => {
+                pantry.push(line);
+                continue;
+            }
+        };
+
+        match grouped
+            .iter_mut()
+            .find(|existing| existing.name == parsed.name && existing.unit == parsed.unit)
+        {
+            Some(existing) => existing.quantity = Some(existing.quantity.unwrap_or(0.0) + quantity),
+            None => grouped.push(parsed),
+        }
+    }
+
+    (grouped, pantry)
+}
+
+/// Format a quantity without a needless ".00" for whole numbers.
+fn format_quantity(quantity: f64) -> String {
+    if (quantity.fract()).abs() < f64::EPSILON {
+        format!("{}", quantity as i64)
+    } else {
+        format!("{:.2}", quantity)
+    }
+}
+
+/// Capitalize the first letter of a grouped ingredient's name for display.
+fn titlecase(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// Generate a consolidated, deduplicated shopping list from a week's scraped
+/// ingredient lines.
+///
+/// # Arguments
+/// * ingredients - Vector of raw ingredient lines scraped from recipe pages
+/// * config - resolved rendering configuration (output dir)
+///
+/// # Returns
+/// * On success, an empty Ok() is returned.
+/// * On Failure, an Err() containing (potentially) useful information is returned.
+///
+pub fn write_ingredients(ingredients: Vec<String>, config: &Config) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(&config.output_dir)?;
+    let file = config.output_dir.join("groceries.txt");
+    let mut file = File::create(file)?;
+
+    let (grouped, pantry) = aggregate_ingredients(ingredients);
+
+    for item in &grouped {
+        let name = titlecase(&item.name);
+        let line = match (item.quantity, &item.unit) {
+            (Some(quantity), Some(unit)) => format!("{} {} {}", format_quantity(quantity), unit, name),
+            (Some(quantity), None) => format!("{} {}", format_quantity(quantity), name),
+            (None, _) => name,
+        };
+        file.write_all(format!("{}\n", line).as_bytes())?;
+    }
+
+    if !pantry.is_empty() {
+        file.write_all(b"\nCheck your pantry:\n")?;
+        for item in pantry {
+            file.write_all(format!("{}\n", item).as_bytes())?;
+        }
+    }
+
+    Ok(())
+}
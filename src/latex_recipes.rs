@@ -1,179 +1,171 @@
-/// Module to handle generating latex files for recipes
-use chrono::Local;
-use select::document::Document;
-use select::predicate::{Class, Name, Predicate};
+/// Module to render parsed recipes as LaTeX and write them out to a document
+use crate::config::Config;
+use crate::recipe::Recipe;
 use std::error::Error;
 use std::fs;
 use std::fs::File;
 use std::io::copy;
 use std::io::prelude::*;
 use std::io::Cursor;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-const DOCUMENT_BEGIN: &str = r#"
-\documentclass[12pt]{article}
+const DOCUMENT_END: &str = r#"
+\end{document}
+"#;
 
-\usepackage{fullpage}
-\usepackage{fontspec}
-\usepackage{multicol}
-\usepackage{graphicx}
+/// Build the document preamble for the given font and font size.
+fn document_begin(main_font: &str, font_size: u32) -> String {
+    format!(
+        r#"
+\documentclass[{font_size}pt]{{article}}
 
-\setmainfont{Andika}
+\usepackage{{fullpage}}
+\usepackage{{fontspec}}
+\usepackage{{multicol}}
+\usepackage{{graphicx}}
 
-\pagestyle{empty}
+\setmainfont{{{main_font}}}
 
-\begin{document}
-"#;
+\pagestyle{{empty}}
 
-const DOCUMENT_END: &str = r#"
-\end{document}
-"#;
+\begin{{document}}
+"#
+    )
+}
 
-/// Collect the image URL for a recipe, if any.
+/// Download a recipe's image into `dir`, returning its filename on success.
 ///
 /// # Arguments
-/// * recipe - the parsed document representing the recipe
-///
-/// * On success, a String containing the url of the image.
-/// * On Failure, None.
-fn get_image_url(recipe: &Document) -> Option<String> {
-    match recipe
-        .find(Class("recipe_image").descendant(Name("img")))
-        .next()
-    {
-        Some(img) => Some(img.attr("src")?.to_string()),
-        None => None,
-    }
+/// * image_url - URL of the image to download
+/// * dir - directory to download the image into
+///
+/// # Returns
+/// * On success, an Ok() containing the filename the image was saved as.
+/// * On Failure, an Err() containing (potentially) useful information is returned.
+///
+fn download_image(image_url: &str, dir: &Path) -> Result<String, Box<dyn Error>> {
+    let split_url: Vec<&str> = image_url.split('/').collect();
+    let image_filename = match split_url.last() {
+        Some(image_filename) => image_filename,
+        None => return Err(format!("Unable to figure out the filename in {image_url}").into()),
+    };
+
+    let image_path = dir.join(image_filename);
+    let mut image_dest = File::create(image_path)?;
+    let mut image_content = Cursor::new(reqwest::blocking::get(image_url)?.bytes()?);
+    copy(&mut image_content, &mut image_dest)?;
+
+    Ok(image_filename.to_string())
 }
 
-/// Generate a LaTex fragment for this recipe, and get any images used in it
+/// Escape the characters LaTeX treats specially, so scraped text can be safely
+/// interpolated into a document without corrupting it or breaking out into math
+/// mode, comments, etc.
 ///
 /// # Arguments
-/// * recipe - the parsed document representing the recipe
+/// * text - the raw, unescaped text to interpolate into a LaTeX document
 ///
 /// # Returns
-/// * On success, a String containing the LaTex Document fragment
-///   describing the recipe is returned.
-/// * On Failure, an Err() containing (potentially) useful information is returned.
+/// * A String with every special character replaced by its LaTeX-safe form.
 ///
-pub fn get_recipe(recipe: Document) -> Result<String, Box<dyn Error>> {
-    let date = Local::now().format("%Y%m%d");
-    fs::create_dir_all(format!("{}", date))?;
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
 
-    // Get the recipe title first, because we use it for error messages later.
-    // If the recipe has no title, this is unrecoverable.
-    let title = match recipe.find(Class("mainTitle")).next() {
-        Some(title) => title.text(),
-        None => return Err("Unable to find title.".into()),
-    };
-
-    // Generate the LaTeX for the recipe
-    let mut recipe_latex: String = String::new();
-
-    // Get the the image URL for the recipe, if any
-    let image_url = get_image_url(&recipe);
-
-    // Download the image, if any
-    match image_url {
-        Some(url) => {
-            let url_copy = url.clone();
-            let split_url: Vec<&str> = url.split("/").collect();
-            match split_url.last() {
-                Some(image_filename) => {
-                    let image_path = PathBuf::from(format!("{}/{}", date, image_filename));
-                    let mut image_dest = File::create(image_path)?;
-                    let mut image_content = Cursor::new(reqwest::blocking::get(url_copy)?.bytes()?);
-                    copy(&mut image_content, &mut image_dest)?;
-
-                    // Start with the image at the top
-                    recipe_latex.push_str(
-                        format!(
-                    "\\begin{{center}}\\includegraphics[height=3in]{{{}}}\\end{{center}}\n\n",
-                    image_filename
-                )
-                        .as_str(),
-                    );
-                }
-                None => eprintln!(
-                    "WARNING: Unable to figure out the filename in {url_copy}, not downloading."
-                ),
-            };
+    for ch in text.chars() {
+        match ch {
+            '%' => escaped.push_str("\\%"),
+            '$' => escaped.push_str("\\$"),
+            '&' => escaped.push_str("\\&"),
+            '#' => escaped.push_str("\\#"),
+            '_' => escaped.push_str("\\_"),
+            '{' => escaped.push_str("\\{"),
+            '}' => escaped.push_str("\\}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            other => escaped.push(other),
         }
-        // This is recoverable - just warn that we don't have an image.
-        None => eprintln!("WARNING: No image for recipe: \"{title}\""),
     }
 
-    // And then add the recipe name, with the optional side dish below it, slightly smaller.
-    let subtitle_match = recipe.find(Class("sideTitle")).next();
-    let mut has_side = false;
+    escaped
+}
 
-    recipe_latex.push_str(format!("{{\\noindent\\Large {}}}\n\n", title).as_str());
+/// Render a recipe as a LaTeX fragment, with an already-downloaded image (if any)
+/// referenced by its local filename.
+///
+/// # Arguments
+/// * recipe - the recipe to render
+/// * image_filename - the local filename of the recipe's downloaded image, if any
+/// * image_height - the LaTeX length (e.g. "3in") to render the image at
+///
+/// # Returns
+/// * A String containing the LaTeX fragment describing the recipe.
+///
+fn render_latex_fragment(
+    recipe: &Recipe,
+    image_filename: Option<&str>,
+    image_height: &str,
+) -> String {
+    let mut recipe_latex = String::new();
+
+    if let Some(image_filename) = image_filename {
+        recipe_latex.push_str(
+            format!(
+                "\\begin{{center}}\\includegraphics[height={image_height}]{{{}}}\\end{{center}}\n\n",
+                image_filename
+            )
+            .as_str(),
+        );
+    }
+
+    // Add the recipe name, with the optional side dish below it, slightly smaller.
+    recipe_latex.push_str(
+        format!("{{\\noindent\\Large {}}}\n\n", escape_latex(&recipe.title)).as_str(),
+    );
     recipe_latex.push_str("\\medskip\n".to_string().as_str());
-    if let Some(subtitle) = subtitle_match {
-        recipe_latex.push_str(format!("{{\\noindent\\large {}}}\n\n", subtitle.text()).as_str());
+    if let Some(side_title) = &recipe.side_title {
+        recipe_latex.push_str(
+            format!("{{\\noindent\\large {}}}\n\n", escape_latex(side_title)).as_str(),
+        );
         recipe_latex.push_str("\\medskip\n".to_string().as_str());
-        has_side = true;
     }
 
-    // Get and emit times
-
-    let times = recipe.find(Class("times").descendant(Name("time")));
-
-    for time in times {
-        recipe_latex.push_str(format!("{} ", time.text()).as_str());
+    // Emit times
+    for time in &recipe.times {
+        recipe_latex.push_str(format!("{} ", escape_latex(time)).as_str());
     }
 
     recipe_latex.push_str("\n\n\\bigskip\n".to_string().as_str());
 
-    // Get and emit main recipe
-    let main_recipe_ingredients = recipe.find(
-        Class("mainInformation")
-            .descendant(Class("ingredients"))
-            .descendant(Name("li")),
-    );
-    let main_recipe_instructions = recipe.find(
-        Class("mainInformation")
-            .descendant(Class("instructions"))
-            .descendant(Name("li")),
-    );
-
+    // Emit main recipe
     recipe_latex.push_str("{\\noindent\\large Ingredients}\n".to_string().as_str());
     recipe_latex.push_str("\\begin{itemize}\n".to_string().as_str());
-    for ingredient in main_recipe_ingredients {
-        recipe_latex.push_str(format!("    \\item[] {}\n", ingredient.text()).as_str());
+    for ingredient in &recipe.main_ingredients {
+        recipe_latex.push_str(format!("    \\item[] {}\n", escape_latex(ingredient)).as_str());
     }
     recipe_latex.push_str("\\end{itemize}\n".to_string().as_str());
     recipe_latex.push_str("\\bigskip\n".to_string().as_str());
     recipe_latex.push_str("{\\noindent\\large Instructions}\n".to_string().as_str());
     recipe_latex.push_str("\\begin{enumerate}\n".to_string().as_str());
-    for instruction in main_recipe_instructions {
-        recipe_latex.push_str(format!("    \\item {}\n", instruction.text()).as_str());
+    for instruction in &recipe.main_instructions {
+        recipe_latex.push_str(format!("    \\item {}\n", escape_latex(instruction)).as_str());
     }
     recipe_latex.push_str("\\end{enumerate}\n".to_string().as_str());
 
     recipe_latex.push_str("\\bigskip\n".to_string().as_str());
 
-    // Get and emit side recipe, if it exists
-    let side_recipe_ingredients = recipe.find(
-        Class("side_dish_section")
-            .descendant(Class("ingredients"))
-            .descendant(Name("li")),
-    );
-    let side_recipe_instructions = recipe.find(
-        Class("side_dish_section")
-            .descendant(Class("instructions"))
-            .descendant(Name("li")),
-    );
-
-    if has_side {
+    // Emit side recipe, if it exists
+    if recipe.side_title.is_some() {
         recipe_latex.push_str(
             "{\\noindent\\large Side Dish Ingredients}\n"
                 .to_string()
                 .as_str(),
         );
         recipe_latex.push_str("\\begin{itemize}\n".to_string().as_str());
-        for ingredient in side_recipe_ingredients {
-            recipe_latex.push_str(format!("    \\item[] {}\n", ingredient.text()).as_str());
+        for ingredient in &recipe.side_ingredients {
+            recipe_latex
+                .push_str(format!("    \\item[] {}\n", escape_latex(ingredient)).as_str());
         }
         recipe_latex.push_str("\\end{itemize}\n".to_string().as_str());
         recipe_latex.push_str("\\bigskip\n".to_string().as_str());
@@ -183,37 +175,104 @@ pub fn get_recipe(recipe: Document) -> Result<String, Box<dyn Error>> {
                 .as_str(),
         );
         recipe_latex.push_str("\\begin{enumerate}\n".to_string().as_str());
-        for instruction in side_recipe_instructions {
-            recipe_latex.push_str(format!("    \\item {}\n", instruction.text()).as_str());
+        for instruction in &recipe.side_instructions {
+            recipe_latex.push_str(format!("    \\item {}\n", escape_latex(instruction)).as_str());
         }
         recipe_latex.push_str("\\end{enumerate}\n".to_string().as_str());
     }
 
-    Ok(recipe_latex)
+    recipe_latex
 }
 
-/// Generate a LaTex document for our recipes
+/// Render a recipe as a standalone LaTeX fragment, without downloading its image.
+///
+/// Useful when the fragment is only being displayed (e.g. `emeals show`), rather
+/// than written alongside a downloaded image into an output directory; use
+/// `write_recipes` instead when the image should be included.
 ///
 /// # Arguments
-/// * recipes - a vector of LaTeX fragement recipe strings
+/// * recipe - the recipe to render
+/// * config - resolved rendering configuration (image height)
 ///
 /// # Returns
-/// * On success, an empty Ok() is returned.
+/// * A String containing the LaTeX fragment describing the recipe.
+///
+pub fn render_latex(recipe: &Recipe, config: &Config) -> String {
+    render_latex_fragment(recipe, None, &config.image_height)
+}
+
+/// Generate a LaTex document for our recipes, downloading each recipe's image
+/// alongside it.
+///
+/// # Arguments
+/// * recipes - a vector of recipes to render
+/// * config - resolved rendering configuration (font, size, image height, output dir)
+///
+/// # Returns
+/// * On success, an Ok() containing the directory the document was written into.
 /// * On Failure, an Err() containing (potentially) useful information is returned.
 ///
-pub fn write_recipes(recipes: Vec<String>) -> Result<(), Box<dyn Error>> {
-    let date = Local::now().format("%Y%m%d");
-    fs::create_dir_all(format!("{}", date))?;
-    let file = PathBuf::from(format!("{}/recipes.tex", date));
+pub fn write_recipes(recipes: Vec<Recipe>, config: &Config) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = &config.output_dir;
+    fs::create_dir_all(dir)?;
+    let file = dir.join("recipes.tex");
     let mut file = File::create(file)?;
 
-    file.write_all(DOCUMENT_BEGIN.as_bytes())?;
+    file.write_all(document_begin(&config.main_font, config.font_size).as_bytes())?;
 
     for recipe in recipes {
-        file.write_all(format!("{}\n\\newpage\n", recipe).as_bytes())?;
+        let image_filename = match &recipe.image_url {
+            Some(image_url) => match download_image(image_url, dir) {
+                Ok(image_filename) => Some(image_filename),
+                Err(error) => {
+                    eprintln!(
+                        "WARNING: Unable to download image for recipe \"{}\": {error}",
+                        recipe.title
+                    );
+                    None
+                }
+            },
+            None => {
+                eprintln!("WARNING: No image for recipe: \"{}\"", recipe.title);
+                None
+            }
+        };
+
+        let recipe_latex =
+            render_latex_fragment(&recipe, image_filename.as_deref(), &config.image_height);
+        file.write_all(format!("{}\n\\newpage\n", recipe_latex).as_bytes())?;
     }
 
     file.write_all(DOCUMENT_END.as_bytes())?;
 
+    Ok(dir.clone())
+}
+
+/// Compile `recipes.tex` in `dir` to a PDF using `engine` (e.g. `xelatex` or `tectonic`).
+///
+/// # Arguments
+/// * dir - directory containing recipes.tex, as returned by `write_recipes`
+/// * engine - name of the LaTeX engine binary to invoke
+///
+/// # Returns
+/// * On success, an empty Ok() is returned.
+/// * On Failure, an Err() containing (potentially) useful information is returned.
+///
+pub fn compile_pdf(dir: &Path, engine: &str) -> Result<(), Box<dyn Error>> {
+    let output = Command::new(engine)
+        .current_dir(dir)
+        .arg("recipes.tex")
+        .output()
+        .map_err(|error| format!("Could not run \"{engine}\": {error}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{engine} failed to compile recipes.tex:\n{}\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .into());
+    }
+
     Ok(())
 }
@@ -1,25 +1,144 @@
 //! Program to parse a list of eMeals URLs and generate recipes from them.
 
-use chrono::Local;
 use select::document::Document;
-use select::predicate::{Class, Name, Predicate};
 use std::error::Error;
-use std::fs;
 use std::fs::File;
-use std::io::{prelude::*, read_to_string};
+use std::io::read_to_string;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use structopt::StructOpt;
 
+mod config;
+mod groceries;
 mod latex_recipes;
+mod markdown_recipes;
+mod recipe;
+
+use recipe::Recipe;
+
+/// CLI flags shared by subcommands that render or write output, controlling
+/// fonts, image size, and where output goes.
+#[derive(StructOpt)]
+struct ConfigArgs {
+    /// Path to an optional TOML config file with rendering overrides
+    #[structopt(long, parse(from_os_str))]
+    config: Option<PathBuf>,
+
+    /// Main document font (LaTeX output only)
+    #[structopt(long)]
+    main_font: Option<String>,
+
+    /// Document font size in points (LaTeX output only)
+    #[structopt(long)]
+    font_size: Option<u32>,
+
+    /// Recipe image height, e.g. "3in" (LaTeX output only)
+    #[structopt(long)]
+    image_height: Option<String>,
+
+    /// Output directory name; defaults to today's date (YYYYMMDD)
+    #[structopt(long)]
+    output_dir: Option<String>,
+}
+
+impl ConfigArgs {
+    fn into_overrides(self) -> config::ConfigOverrides {
+        config::ConfigOverrides {
+            config_file: self.config,
+            main_font: self.main_font,
+            font_size: self.font_size,
+            image_height: self.image_height,
+            output_dir: self.output_dir,
+        }
+    }
+}
+
+/// Output format for the `show` subcommand
+enum OutputFormat {
+    Latex,
+    Markdown,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "latex" => Ok(OutputFormat::Latex),
+            "markdown" => Ok(OutputFormat::Markdown),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(format!(
+                "Unknown format \"{other}\", expected latex, markdown, or json"
+            )),
+        }
+    }
+}
 
 /// Command line arguments
 #[derive(StructOpt)]
-struct Args {
-    /// (Input) the file containing our list of URLs
-    #[structopt(parse(from_os_str))]
-    file: PathBuf,
+enum Args {
+    /// Fetch recipes, write them to a recipes.tex file, and compile it to a PDF
+    Recipes {
+        /// (Input) the file containing our list of URLs
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+
+        /// Don't compile the generated recipes.tex to a PDF
+        #[structopt(long)]
+        no_compile: bool,
+
+        /// LaTeX engine to compile recipes.tex with
+        #[structopt(long, default_value = "xelatex")]
+        engine: String,
+
+        #[structopt(flatten)]
+        config_args: ConfigArgs,
+    },
+    /// Fetch recipes and write a consolidated groceries.txt file
+    Groceries {
+        /// (Input) the file containing our list of URLs
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+
+        #[structopt(flatten)]
+        config_args: ConfigArgs,
+    },
+    /// Fetch recipes and print their titles, without generating anything
+    List {
+        /// (Input) the file containing our list of URLs
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Fetch recipes and emit one of them to stdout as LaTeX, Markdown, or JSON
+    Show {
+        /// (Input) the file containing our list of URLs
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+
+        /// Title of the recipe to show
+        title: String,
+
+        /// Output format: latex, markdown, or json
+        #[structopt(long, default_value = "latex")]
+        format: OutputFormat,
+
+        /// Recipe image height, e.g. "3in" (LaTeX output only)
+        #[structopt(long)]
+        image_height: Option<String>,
+    },
+}
+
+/// A recipe that has been fetched and parsed from a URL.
+pub struct ProcessedUrl {
+    /// The recipe's title, as scraped from the page
+    pub title: String,
+    /// All ingredients for the recipe, main dish and side dish alike
+    pub ingredients: Vec<String>,
+    /// The recipe itself, parsed into our format-agnostic model
+    pub recipe: Recipe,
 }
 
 /// Read our file in to a vector of URLs
@@ -51,17 +170,11 @@ fn read_file(filename: PathBuf) -> Result<Vec<String>, Box<dyn Error>> {
 ///
 /// # Arguments
 /// * url - URL for which we should get the HTML and generate appropriate output
-/// * ingredients - reference counted mutexed vector of ingredient strings
-/// * recipes - reference counted mutexed vector of recipes as LaTeX fragments
 ///
 /// # Returns
-/// * On success, an empty Ok() is returned.
+/// * On success, an Ok() containing the parsed ProcessedUrl for this recipe.
 /// * On Failure, an Err() containing (potentially) useful information is returned.
-fn process_url(
-    url: &String,
-    ingredients: Arc<Mutex<Vec<String>>>,
-    recipes: Arc<Mutex<Vec<String>>>,
-) -> Result<(), Box<dyn Error>> {
+fn process_url(url: &String) -> Result<ProcessedUrl, Box<dyn Error>> {
     let client = reqwest::blocking::ClientBuilder::new()
         .user_agent("Mozilla/5.0")
         .build()?;
@@ -70,89 +183,95 @@ fn process_url(
 
     let document = Document::from_read(resp)?;
 
-    // Get all ingredients - main recipe and side dish
-    let all_ingredients = document.find(Class("ingredients").descendant(Name("li")));
+    let recipe = recipe::get_recipe(document)?;
 
-    // Note - we lock the output list here to avoid 2 things:
-    // 1. lots of locking and unlocking
-    // 2. interleaving the ingredients from different recipes
+    let title = recipe.title.clone();
+    let ingredients = recipe
+        .main_ingredients
+        .iter()
+        .chain(recipe.side_ingredients.iter())
+        .cloned()
+        .collect();
 
-    match ingredients.lock() {
-        Ok(mut ingredients) => {
-            for ingredient in all_ingredients {
-                ingredients.push(ingredient.text());
-            }
-        }
-        Err(error) => {
-            return Err(format!(
-                "Failed to acquire ingredients mutex for adding ingredients list: {}",
-                error
-            )
-            .into())
-        }
-    };
+    Ok(ProcessedUrl {
+        title,
+        ingredients,
+        recipe,
+    })
+}
 
-    // Debug doc dump...
-    // println!("{:?}", document);
-
-    match recipes.lock() {
-        Ok(mut recipe) => recipe.push(latex_recipes::get_recipe(document)?),
-        Err(error) => {
-            return Err(format!(
-                "Failed to acquire recipe mutex for writing an entry: {}",
-                error
-            )
-            .into())
-        }
-    };
+/// A URL that failed to process, and why.
+pub struct UrlFailure {
+    /// The URL that failed
+    pub url: String,
+    /// The error encountered while processing it
+    pub error: String,
+}
 
-    Ok(())
+/// The outcome of processing a batch of URLs: the recipes we managed to parse,
+/// and the URLs that failed along with their errors.
+#[derive(Default)]
+pub struct UrlResults {
+    /// URLs that were successfully fetched and parsed
+    pub succeeded: Vec<ProcessedUrl>,
+    /// URLs that failed, and why
+    pub failed: Vec<UrlFailure>,
 }
 
-/// Generate a text file for our ingredients for the week
+/// Print a summary of how many URLs succeeded and failed, and the specific
+/// error for each failure.
 ///
 /// # Arguments
-/// * ingredients - Vector of ingredients to be put into our LaTeX document
+/// * results - the UrlResults to report on
 ///
-/// # Returns
-/// * On success, an empty Ok() is returned.
-/// * On Failure, an Err() containing (potentially) useful information is returned.
-///
-pub fn write_ingredients(ingredients: Vec<String>) -> Result<(), Box<dyn Error>> {
-    let date = Local::now().format("%Y%m%d");
-    fs::create_dir_all(format!("{}", date))?;
-    let file = PathBuf::from(format!("{}/groceries.txt", date));
-
-    let mut file = File::create(file)?;
+fn report_url_results(results: &UrlResults) {
+    let total = results.succeeded.len() + results.failed.len();
+    eprintln!(
+        "Processed {} of {total} URLs successfully.",
+        results.succeeded.len()
+    );
 
-    for ingredient in ingredients {
-        file.write_all(format!("{}\n", ingredient).as_bytes())?;
+    if !results.failed.is_empty() {
+        eprintln!("The following URLs failed:");
+        for failure in &results.failed {
+            eprintln!("  {}: {}", failure.url, failure.error);
+        }
     }
-
-    Ok(())
 }
 
-/// Spin up parallel tokio tasks for URL processing, one for each URL in our vector
+/// Spin up parallel threads for URL processing, one for each URL in our vector, and
+/// collect the parsed results for the caller to consume however it needs.
 ///
 /// # Arguments
 /// * urls - Vector of URLs for which we should get and process the HTML
 ///
 /// # Returns
-/// * On success, an empty Ok() is returned.
+/// * On success, an Ok() containing the UrlResults for the whole batch - this is
+///   returned even if some (or all) of the URLs failed to process.
 /// * On Failure, an Err() containing (potentially) useful information is returned.
 ///
-fn get_urls(urls: Vec<String>) -> Result<(), Box<dyn Error>> {
+pub fn get_urls(urls: Vec<String>) -> Result<UrlResults, Box<dyn Error>> {
     let mut tasks: Vec<thread::JoinHandle<_>> = Vec::new();
-    let ingredients: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
-    let recipes: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+    let results: Arc<Mutex<UrlResults>> = Arc::new(Mutex::new(UrlResults::default()));
 
     for url in urls {
-        // New variable to receive clones before being moved into the function
-        let my_ingredient = ingredients.clone();
-        let my_recipe = recipes.clone();
+        // New variable to receive the clone before being moved into the function
+        let my_results = results.clone();
         tasks.push(thread::spawn(move || {
-            process_url(&url, my_ingredient, my_recipe)
-                .unwrap_or_else(|_| eprintln!("Error processing URL: {}", url));
+            let outcome = process_url(&url);
+            match my_results.lock() {
+                Ok(mut results) => match outcome {
+                    Ok(result) => results.succeeded.push(result),
+                    Err(error) => results.failed.push(UrlFailure {
+                        url,
+                        error: error.to_string(),
+                    }),
+                },
+                Err(error) => eprintln!(
+                    "Failed to acquire results mutex for adding {}: {}",
+                    url, error
+                ),
+            }
         }));
     }
 
@@ -165,31 +284,10 @@ fn get_urls(urls: Vec<String>) -> Result<(), Box<dyn Error>> {
         };
     }
 
-    // Ingredients and recipes should now be populated and unused by any subthreads,
-    // so generate their respective files' ingredients list.
-    match ingredients.lock() {
-        Ok(ingredients) => write_ingredients(ingredients.to_vec())?,
-        Err(error) => {
-            return Err(format!(
-                "Failed to acquire ingredients mutex for writing to file: {}",
-                error
-            )
-            .into())
-        }
+    match Arc::try_unwrap(results) {
+        Ok(results) => Ok(results.into_inner()?),
+        Err(_) => Err("Failed to reclaim results after all tasks completed".into()),
     }
-
-    match recipes.lock() {
-        Ok(recipes) => latex_recipes::write_recipes(recipes.to_vec())?,
-        Err(error) => {
-            return Err(format!(
-                "Failed to acquire recipe mutex for writing to file: {}",
-                error
-            )
-            .into())
-        }
-    }
-
-    Ok(())
 }
 
 /// Main function
@@ -199,11 +297,92 @@ fn get_urls(urls: Vec<String>) -> Result<(), Box<dyn Error>> {
 /// * On Failure, an Err() containing (potentially) useful information is returned.
 ///
 fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::from_args();
+    let mut any_failed = false;
+
+    match Args::from_args() {
+        Args::Recipes {
+            file,
+            no_compile,
+            engine,
+            config_args,
+        } => {
+            let config = config::resolve(config_args.into_overrides())?;
+            let urls = read_file(file)?;
+            let results = get_urls(urls)?;
+            report_url_results(&results);
+            any_failed = !results.failed.is_empty();
+
+            let recipes = results
+                .succeeded
+                .into_iter()
+                .map(|result| result.recipe)
+                .collect();
+            let dir = latex_recipes::write_recipes(recipes, &config)?;
+            if !no_compile {
+                latex_recipes::compile_pdf(&dir, &engine)?;
+            }
+        }
+        Args::Groceries { file, config_args } => {
+            let config = config::resolve(config_args.into_overrides())?;
+            let urls = read_file(file)?;
+            let results = get_urls(urls)?;
+            report_url_results(&results);
+            any_failed = !results.failed.is_empty();
+
+            let ingredients = results
+                .succeeded
+                .into_iter()
+                .flat_map(|result| result.ingredients)
+                .collect();
+            groceries::write_ingredients(ingredients, &config)?;
+        }
+        Args::List { file } => {
+            let urls = read_file(file)?;
+            let results = get_urls(urls)?;
+            report_url_results(&results);
+            any_failed = !results.failed.is_empty();
+
+            for result in results.succeeded {
+                println!("{}", result.title);
+            }
+        }
+        Args::Show {
+            file,
+            title,
+            format,
+            image_height,
+        } => {
+            let config = config::resolve(config::ConfigOverrides {
+                image_height,
+                ..Default::default()
+            })?;
+            let urls = read_file(file)?;
+            let results = get_urls(urls)?;
+            report_url_results(&results);
+            any_failed = !results.failed.is_empty();
 
-    let urls = read_file(args.file)?;
+            match results
+                .succeeded
+                .into_iter()
+                .find(|result| result.title == title)
+            {
+                Some(result) => match format {
+                    OutputFormat::Latex => {
+                        println!("{}", latex_recipes::render_latex(&result.recipe, &config))
+                    }
+                    OutputFormat::Markdown => {
+                        println!("{}", markdown_recipes::render_markdown(&result.recipe))
+                    }
+                    OutputFormat::Json => println!("{}", recipe::render_json(&result.recipe)?),
+                },
+                None => return Err(format!("No recipe found with title \"{title}\"").into()),
+            }
+        }
+    }
 
-    get_urls(urls)?;
+    if any_failed {
+        std::process::exit(1);
+    }
 
     Ok(())
 }
@@ -0,0 +1,55 @@
+/// Module to render parsed recipes as Markdown
+use crate::recipe::Recipe;
+
+/// Render a recipe as a Markdown fragment.
+///
+/// # Arguments
+/// * recipe - the recipe to render
+///
+/// # Returns
+/// * A String containing the Markdown fragment describing the recipe.
+///
+pub fn render_markdown(recipe: &Recipe) -> String {
+    let mut markdown = String::new();
+
+    markdown.push_str(format!("# {}\n\n", recipe.title).as_str());
+    if let Some(side_title) = &recipe.side_title {
+        markdown.push_str(format!("## {}\n\n", side_title).as_str());
+    }
+
+    if let Some(image_url) = &recipe.image_url {
+        markdown.push_str(format!("![{}]({})\n\n", recipe.title, image_url).as_str());
+    }
+
+    if !recipe.times.is_empty() {
+        markdown.push_str(format!("{}\n\n", recipe.times.join(" ")).as_str());
+    }
+
+    markdown.push_str("## Ingredients\n\n");
+    for ingredient in &recipe.main_ingredients {
+        markdown.push_str(format!("- {}\n", ingredient).as_str());
+    }
+    markdown.push('\n');
+
+    markdown.push_str("## Instructions\n\n");
+    for (index, instruction) in recipe.main_instructions.iter().enumerate() {
+        markdown.push_str(format!("{}. {}\n", index + 1, instruction).as_str());
+    }
+    markdown.push('\n');
+
+    if recipe.side_title.is_some() {
+        markdown.push_str("## Side Dish Ingredients\n\n");
+        for ingredient in &recipe.side_ingredients {
+            markdown.push_str(format!("- {}\n", ingredient).as_str());
+        }
+        markdown.push('\n');
+
+        markdown.push_str("## Side Dish Instructions\n\n");
+        for (index, instruction) in recipe.side_instructions.iter().enumerate() {
+            markdown.push_str(format!("{}. {}\n", index + 1, instruction).as_str());
+        }
+        markdown.push('\n');
+    }
+
+    markdown
+}
@@ -0,0 +1,132 @@
+//! Plain data model for a parsed recipe, independent of any output format.
+use select::document::Document;
+use select::predicate::{Class, Name, Predicate};
+use serde::Serialize;
+use std::error::Error;
+
+/// A recipe scraped from an eMeals page, with its side dish (if any) folded in.
+#[derive(Serialize)]
+pub struct Recipe {
+    /// The recipe's title
+    pub title: String,
+    /// The side dish's title, if this recipe has one
+    pub side_title: Option<String>,
+    /// Prep/cook times, as scraped (e.g. "Prep: 10 min")
+    pub times: Vec<String>,
+    /// URL of the recipe's photo, if any
+    pub image_url: Option<String>,
+    /// Ingredients for the main dish
+    pub main_ingredients: Vec<String>,
+    /// Instructions for the main dish
+    pub main_instructions: Vec<String>,
+    /// Ingredients for the side dish, empty if there is no side dish
+    pub side_ingredients: Vec<String>,
+    /// Instructions for the side dish, empty if there is no side dish
+    pub side_instructions: Vec<String>,
+}
+
+/// Collect the image URL for a recipe, if any.
+///
+/// # Arguments
+/// * document - the parsed document representing the recipe
+///
+/// # Returns
+/// * On success, a String containing the url of the image.
+/// * On Failure, None.
+fn get_image_url(document: &Document) -> Option<String> {
+    match document
+        .find(Class("recipe_image").descendant(Name("img")))
+        .next()
+    {
+        Some(img) => Some(img.attr("src")?.to_string()),
+        None => None,
+    }
+}
+
+/// Parse a recipe page into a plain `Recipe`, with no rendering or I/O attached.
+///
+/// # Arguments
+/// * document - the parsed document representing the recipe
+///
+/// # Returns
+/// * On success, a Recipe containing the scraped recipe.
+/// * On Failure, an Err() containing (potentially) useful information is returned.
+///
+pub fn get_recipe(document: Document) -> Result<Recipe, Box<dyn Error>> {
+    // Get the recipe title first, because we use it for error messages elsewhere.
+    // If the recipe has no title, this is unrecoverable.
+    let title = match document.find(Class("mainTitle")).next() {
+        Some(title) => title.text(),
+        None => return Err("Unable to find title.".into()),
+    };
+
+    let image_url = get_image_url(&document);
+
+    let side_title = document
+        .find(Class("sideTitle"))
+        .next()
+        .map(|subtitle| subtitle.text());
+
+    let times = document
+        .find(Class("times").descendant(Name("time")))
+        .map(|time| time.text())
+        .collect();
+
+    let main_ingredients = document
+        .find(
+            Class("mainInformation")
+                .descendant(Class("ingredients"))
+                .descendant(Name("li")),
+        )
+        .map(|ingredient| ingredient.text())
+        .collect();
+    let main_instructions = document
+        .find(
+            Class("mainInformation")
+                .descendant(Class("instructions"))
+                .descendant(Name("li")),
+        )
+        .map(|instruction| instruction.text())
+        .collect();
+
+    let side_ingredients = document
+        .find(
+            Class("side_dish_section")
+                .descendant(Class("ingredients"))
+                .descendant(Name("li")),
+        )
+        .map(|ingredient| ingredient.text())
+        .collect();
+    let side_instructions = document
+        .find(
+            Class("side_dish_section")
+                .descendant(Class("instructions"))
+                .descendant(Name("li")),
+        )
+        .map(|instruction| instruction.text())
+        .collect();
+
+    Ok(Recipe {
+        title,
+        side_title,
+        times,
+        image_url,
+        main_ingredients,
+        main_instructions,
+        side_ingredients,
+        side_instructions,
+    })
+}
+
+/// Render a recipe as a JSON string, so it can be piped into other tools.
+///
+/// # Arguments
+/// * recipe - the recipe to serialize
+///
+/// # Returns
+/// * On success, a String containing the recipe as JSON.
+/// * On Failure, an Err() containing (potentially) useful information is returned.
+///
+pub fn render_json(recipe: &Recipe) -> Result<String, Box<dyn Error>> {
+    Ok(serde_json::to_string_pretty(recipe)?)
+}